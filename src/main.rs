@@ -1,22 +1,41 @@
 use eframe::egui;
 use serialport::SerialPort;
-use std::{sync::{mpsc, Arc, Mutex}, thread, time::Duration};
+use std::{thread, time::Duration};
 use std::process::Command;
 use std::os::unix::process::CommandExt;
 use eframe::egui::plot::{Line, Plot, PlotPoints};
+use crossbeam_channel::{select, Receiver, Sender};
+
+// UI 线程发给串口线程的命令，串口线程独占 SerialPort
+enum SerialPortCmd {
+    Write(Vec<u8>),
+    ResetMcu,
+    SetBaud(u32),
+    Disconnect,
+}
 
 struct SerialMonitorApp {
     available_ports: Vec<String>,
     selected_port: String,
     baud_rate: u32,
     received_data: String,
-    port: Option<Arc<Mutex<Box<dyn SerialPort>>>>,
-    rx: Option<mpsc::Receiver<String>>,
+    cmd_tx: Option<Sender<SerialPortCmd>>,  // 向串口线程发送命令
+    rx: Option<Receiver<String>>,           // 从串口线程接收数据帧
     send_data: String,
     is_hex_input: bool,
     is_hex_display: bool,
+    is_cobs_mode: bool,              // COBS/postcard 二进制协议模式
+    cobs_msg_type: CobsMessageType,  // COBS 帧解码成的消息类型
+    encoding: TextEncoding,  // 文本模式下的字符编码
+    data_bits: serialport::DataBits,
+    parity: serialport::Parity,
+    stop_bits: serialport::StopBits,
+    flow_control: serialport::FlowControl,
     common_baud_rates: Vec<u32>,
     plot_window: PlotWindow,
+    is_logging: bool,             // 是否把收到的帧写入日志文件
+    log_path: String,             // 日志文件路径
+    log_file: Option<std::fs::File>,
 }
 
 impl Default for SerialMonitorApp {
@@ -32,13 +51,23 @@ impl Default for SerialMonitorApp {
             selected_port: String::new(),
             baud_rate: 115200,  // 改为更常用的默认值
             received_data: String::new(),
-            port: None,
+            cmd_tx: None,
             rx: None,
             send_data: String::new(),
             is_hex_input: false,
             is_hex_display: false,
+            is_cobs_mode: false,
+            cobs_msg_type: CobsMessageType::default(),
+            encoding: TextEncoding::default(),
+            data_bits: serialport::DataBits::Eight,
+            parity: serialport::Parity::None,
+            stop_bits: serialport::StopBits::One,
+            flow_control: serialport::FlowControl::None,
             common_baud_rates: vec![1200, 2400, 4800, 9600, 19200, 38400, 57600, 115200],
             plot_window: PlotWindow::default(),
+            is_logging: false,
+            log_path: String::from("serial_log.txt"),
+            log_file: None,
         }
     }
 }
@@ -46,17 +75,26 @@ impl Default for SerialMonitorApp {
 impl eframe::App for SerialMonitorApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         if let Some(rx) = &self.rx {
-            if let Ok(data) = rx.try_recv() {
-                if self.is_hex_display {
-                    let hex_str: String = data.bytes()
-                        .map(|b| format!("{:02X} ", b))
-                        .collect();
-                    self.received_data.push_str(&hex_str);
-                } else {
-                    self.received_data.push_str(&data);
+            match rx.try_recv() {
+                Ok(data) => {
+                    if self.is_hex_display {
+                        let hex_str: String = data.bytes()
+                            .map(|b| format!("{:02X} ", b))
+                            .collect();
+                        self.received_data.push_str(&hex_str);
+                    } else {
+                        self.received_data.push_str(&data);
+                    }
+                    self.received_data.push('\n');
+                    self.log_frame(&data);  // 记录到日志文件
+                    self.parse_data(&data); // 添加数据解析
                 }
-                self.received_data.push('\n');
-                self.parse_data(&data); // 添加数据解析
+                // 串口线程已退出（设备断开）：收尾清理，回到未连接状态
+                Err(crossbeam_channel::TryRecvError::Disconnected) => {
+                    self.cmd_tx = None;
+                    self.rx = None;
+                }
+                Err(crossbeam_channel::TryRecvError::Empty) => {}
             }
         }
 
@@ -84,6 +122,7 @@ impl eframe::App for SerialMonitorApp {
 
             ui.horizontal(|ui| {
                 ui.label("Rate:");
+                let old_baud = self.baud_rate;
                 egui::ComboBox::from_label("")
                     .selected_text(self.baud_rate.to_string())
                     .show_ui(ui, |ui| {
@@ -91,15 +130,58 @@ impl eframe::App for SerialMonitorApp {
                             ui.selectable_value(&mut self.baud_rate, rate, rate.to_string());
                         }
                     });
-                
+
                 // 保留手动输入功能
                 ui.add(egui::DragValue::new(&mut self.baud_rate)
                     .speed(100)
                     .clamp_range(1200..=115200));
+
+                // 连接状态下改动波特率立即下发到串口线程
+                if self.baud_rate != old_baud {
+                    if let Some(cmd_tx) = &self.cmd_tx {
+                        let _ = cmd_tx.send(SerialPortCmd::SetBaud(self.baud_rate));
+                    }
+                }
             });
 
-            if ui.button(if self.port.is_some() { "Stop" } else { "Start" }).clicked() {
-                if self.port.is_none() {
+            // 线路参数：数据位 / 校验 / 停止位 / 流控
+            // 仅在 connect() 时读取，连接期间禁用以免界面与实际不符
+            ui.horizontal(|ui| {
+                ui.add_enabled_ui(self.cmd_tx.is_none(), |ui| {
+                    use serialport::{DataBits, FlowControl, Parity, StopBits};
+                    egui::ComboBox::from_label("Data")
+                        .selected_text(format!("{:?}", self.data_bits))
+                        .show_ui(ui, |ui| {
+                            for bits in [DataBits::Five, DataBits::Six, DataBits::Seven, DataBits::Eight] {
+                                ui.selectable_value(&mut self.data_bits, bits, format!("{:?}", bits));
+                            }
+                        });
+                    egui::ComboBox::from_label("Parity")
+                        .selected_text(format!("{:?}", self.parity))
+                        .show_ui(ui, |ui| {
+                            for parity in [Parity::None, Parity::Odd, Parity::Even] {
+                                ui.selectable_value(&mut self.parity, parity, format!("{:?}", parity));
+                            }
+                        });
+                    egui::ComboBox::from_label("Stop")
+                        .selected_text(format!("{:?}", self.stop_bits))
+                        .show_ui(ui, |ui| {
+                            for stop in [StopBits::One, StopBits::Two] {
+                                ui.selectable_value(&mut self.stop_bits, stop, format!("{:?}", stop));
+                            }
+                        });
+                    egui::ComboBox::from_label("Flow")
+                        .selected_text(format!("{:?}", self.flow_control))
+                        .show_ui(ui, |ui| {
+                            for flow in [FlowControl::None, FlowControl::Software, FlowControl::Hardware] {
+                                ui.selectable_value(&mut self.flow_control, flow, format!("{:?}", flow));
+                            }
+                        });
+                });
+            });
+
+            if ui.button(if self.cmd_tx.is_some() { "Stop" } else { "Start" }).clicked() {
+                if self.cmd_tx.is_none() {
                     self.connect();
                 } else {
                     self.disconnect();
@@ -109,14 +191,35 @@ impl eframe::App for SerialMonitorApp {
             ui.separator();
 
             // Add send data controls
+            // 接收模式/编码在连接时被快照进读线程，连接期间禁用以免界面与实际不符
+            let connected = self.cmd_tx.is_some();
             ui.horizontal(|ui| {
                 ui.checkbox(&mut self.is_hex_input, "HEX_Input");
                 ui.checkbox(&mut self.is_hex_display, "HEX_Display");
+                ui.add_enabled_ui(!connected, |ui| {
+                    egui::ComboBox::from_label("Enc")
+                        .selected_text(self.encoding.label())
+                        .show_ui(ui, |ui| {
+                            for enc in TextEncoding::ALL {
+                                ui.selectable_value(&mut self.encoding, enc, enc.label());
+                            }
+                        });
+                    ui.checkbox(&mut self.is_cobs_mode, "COBS");
+                    if self.is_cobs_mode {
+                        egui::ComboBox::from_label("Msg")
+                            .selected_text(self.cobs_msg_type.label())
+                            .show_ui(ui, |ui| {
+                                for ty in CobsMessageType::ALL {
+                                    ui.selectable_value(&mut self.cobs_msg_type, ty, ty.label());
+                                }
+                            });
+                    }
+                });
             });
 
             ui.horizontal(|ui| {
                 let text_edit = ui.text_edit_singleline(&mut self.send_data);
-                if ui.button("Send").clicked() && self.port.is_some() {
+                if ui.button("Send").clicked() && self.cmd_tx.is_some() {
                     self.send_data();
                 }
                 if text_edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
@@ -146,10 +249,21 @@ impl eframe::App for SerialMonitorApp {
                 if ui.button("Clean").clicked() {
                     self.received_data.clear();
                 }
-                if ui.button("Reset MCU").clicked() && self.port.is_some() {
+                if ui.button("Reset MCU").clicked() && self.cmd_tx.is_some() {
                     self.reset_device();
                 }
             });
+
+            // 日志记录：勾选后把每一帧追加写入指定文件
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut self.is_logging, "Start Logging").changed() {
+                    self.toggle_logging();
+                }
+                ui.add_enabled(
+                    !self.is_logging,
+                    egui::TextEdit::singleline(&mut self.log_path),
+                );
+            });
         });
 
         // 更新绘图窗口
@@ -168,51 +282,85 @@ impl SerialMonitorApp {
 
         match serialport::new(&self.selected_port, self.baud_rate)
             .timeout(Duration::from_millis(10))
-            .data_bits(serialport::DataBits::Eight)
-            .flow_control(serialport::FlowControl::None)
-            .parity(serialport::Parity::None)
-            .stop_bits(serialport::StopBits::One)
+            .data_bits(self.data_bits)
+            .flow_control(self.flow_control)
+            .parity(self.parity)
+            .stop_bits(self.stop_bits)
             .open()
         {
             Ok(mut port) => {
                 // Set DTR and RTS after port is opened
                 let _ = port.write_data_terminal_ready(false);
                 let _ = port.write_request_to_send(false);
-                
-                let port = Arc::new(Mutex::new(port));
-                let port_clone = Arc::clone(&port);
-                let (tx, rx) = mpsc::channel();
+                let _ = port.clear(serialport::ClearBuffer::All);
 
+                // 数据通道：串口线程 -> UI；命令通道：UI -> 串口线程
+                let (tx, rx) = crossbeam_channel::unbounded::<String>();
+                let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded::<SerialPortCmd>();
+
+                let is_cobs_mode = self.is_cobs_mode;
+                let cobs_msg_type = self.cobs_msg_type;
+                let encoding = self.encoding.encoding();
+
+                // 串口线程独占 port，用 select! 在读串口和处理命令之间多路复用
                 thread::spawn(move || {
                     let mut serial_buf: Vec<u8> = vec![0; 1024];
+                    let mut cobs = CobsDecoder::default();
+                    // 流式解码器内部保留跨读取边界的残余字节，使多字节字符正确拼接
+                    let mut decoder = encoding.new_decoder();
                     loop {
-                        if let Ok(mut port) = port_clone.lock() {
-                            match port.read(serial_buf.as_mut_slice()) {
-                                Ok(t) => {
-                                    if t > 0 {
-                                        let s = String::from_utf8_lossy(&serial_buf[..t]).into_owned();
-                                        let _ = tx.send(s);
+                        select! {
+                            recv(cmd_rx) -> cmd => {
+                                match cmd {
+                                    Ok(SerialPortCmd::Write(data)) => {
+                                        let _ = port.write(&data);
                                     }
+                                    Ok(SerialPortCmd::SetBaud(baud)) => {
+                                        let _ = port.set_baud_rate(baud);
+                                    }
+                                    Ok(SerialPortCmd::ResetMcu) => {
+                                        let _ = port.clear(serialport::ClearBuffer::All);
+                                        let _ = port.write_data_terminal_ready(true);
+                                        thread::sleep(Duration::from_millis(100));
+                                        let _ = port.write_data_terminal_ready(false);
+                                        thread::sleep(Duration::from_millis(100));
+                                    }
+                                    // 通道关闭或显式断开都退出，port 随之被释放
+                                    Ok(SerialPortCmd::Disconnect) | Err(_) => break,
                                 }
-                                Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {
-                                    continue;
-                                }
-                                Err(e) => {
-                                    let _ = tx.send(format!("Error: {}\n", e));
-                                    thread::sleep(Duration::from_millis(100));
-                                    let _ = port.clear(serialport::ClearBuffer::All);
+                            }
+                            // 没有命令时轮询串口读取
+                            default(Duration::from_millis(10)) => {
+                                match port.read(serial_buf.as_mut_slice()) {
+                                    Ok(t) if t > 0 => {
+                                        if is_cobs_mode {
+                                            // 累积字节、按 0x00 分帧、COBS 解码再 postcard 反序列化
+                                            for frame in cobs.push(&serial_buf[..t]) {
+                                                let _ = tx.send(cobs_msg_type.decode(&frame));
+                                            }
+                                        } else {
+                                            let mut s = String::with_capacity(t * 2);
+                                            // last = false：行尾可能是半个多字节字符，留待下次读取
+                                            let _ = decoder.decode_to_string(&serial_buf[..t], &mut s, false);
+                                            if !s.is_empty() {
+                                                let _ = tx.send(s);
+                                            }
+                                        }
+                                    }
+                                    Ok(_) => {}
+                                    Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {}
+                                    // 非超时错误视为设备已断开：通知 UI 并结束线程，不再无限重试
+                                    Err(e) => {
+                                        let _ = tx.send(format!("Disconnected: {}\n", e));
+                                        break;
+                                    }
                                 }
                             }
                         }
-                        thread::sleep(Duration::from_millis(10));
                     }
                 });
 
-                if let Ok(mut port) = port.lock() {
-                    let _ = port.clear(serialport::ClearBuffer::All);
-                }
-
-                self.port = Some(port);
+                self.cmd_tx = Some(cmd_tx);
                 self.rx = Some(rx);
                 self.received_data = "Connected\n".to_string();
             }
@@ -223,16 +371,19 @@ impl SerialMonitorApp {
     }
 
     fn disconnect(&mut self) {
-        self.port = None;
+        if let Some(cmd_tx) = &self.cmd_tx {
+            let _ = cmd_tx.send(SerialPortCmd::Disconnect);
+        }
+        self.cmd_tx = None;
         self.rx = None;
         self.received_data.push_str("Unconnected\n");
     }
 
     fn send_data(&mut self) {
-        if let Some(port) = &self.port {
+        if let Some(cmd_tx) = &self.cmd_tx {
             let data = if self.is_hex_input {
                 // 转换hex字符串为字节
-                let hex_str = self.send_data.replace(" ", "");
+                let hex_str = self.send_data.replace(' ', "");
                 let mut bytes = Vec::new();
                 for i in (0..hex_str.len()).step_by(2) {
                     if i + 2 <= hex_str.len() {
@@ -246,16 +397,15 @@ impl SerialMonitorApp {
                 self.send_data.as_bytes().to_vec()
             };
 
-            if let Ok(mut port) = port.lock() {
-                if port.write(&data).is_ok() {
-                    if self.is_hex_display {
-                        let hex_str: String = data.iter()
-                            .map(|b| format!("{:02X} ", b))
-                            .collect();
-                        self.received_data.push_str(&format!("Send: {}\n", hex_str));
-                    } else {
-                        self.received_data.push_str(&format!("Send: {}\n", self.send_data));
-                    }
+            // 非阻塞发送，写操作由独占 port 的线程执行
+            if cmd_tx.send(SerialPortCmd::Write(data.clone())).is_ok() {
+                if self.is_hex_display {
+                    let hex_str: String = data.iter()
+                        .map(|b| format!("{:02X} ", b))
+                        .collect();
+                    self.received_data.push_str(&format!("Send: {}\n", hex_str));
+                } else {
+                    self.received_data.push_str(&format!("Send: {}\n", self.send_data));
                 }
             }
             self.send_data.clear();
@@ -267,25 +417,16 @@ impl SerialMonitorApp {
             return;
         }
 
-        println!("Trying to parse line: {}", line);
-        let line = line.trim();
-        let mut values = Vec::new();
-
-        if line.starts_with("Pace: FL:") {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            for part in parts {
-                if let Ok(value) = part.parse::<f64>() {
-                    values.push(value);
-                }
-            }
+        let count = self.plot_window.channel_count();
+        if count == 0 {
+            return;
         }
+        self.plot_window.ensure_channels();  // format 改动后重建缓冲区
 
-        println!("Found {} values: {:?}", values.len(), values);
-
-        if values.len() == 4 {
+        let separators = self.plot_window.separators();
+        if let Some(values) = parse_by_format(line.trim(), &separators, count) {
             for (i, &value) in values.iter().enumerate() {
                 self.plot_window.plot_data[i].push(value, self.plot_window.max_points);
-                println!("Updated plot {} with value {}", i, value);
             }
         }
     }
@@ -317,6 +458,17 @@ impl SerialMonitorApp {
                             plot_data.start_time = None;
                         }
                     }
+                    if ui.button("Export CSV").clicked() {
+                        match self.plot_window.export_csv() {
+                            Ok(()) => self
+                                .received_data
+                                .push_str(&format!("Exported: {}\n", self.plot_window.csv_path)),
+                            Err(e) => self
+                                .received_data
+                                .push_str(&format!("Export failed: {}\n", e)),
+                        }
+                    }
+                    ui.add(egui::TextEdit::singleline(&mut self.plot_window.csv_path));
                 });
                 
                 // 添加暂停状态显示
@@ -324,11 +476,14 @@ impl SerialMonitorApp {
                     ui.label(egui::RichText::new("PAUSED").color(egui::Color32::RED));
                 }
 
+                self.plot_window.ensure_channels();
+                let names = self.plot_window.channel_names();
+
                 // 添加调试信息显示
-                for i in 0..4 {
+                for (i, name) in names.iter().enumerate() {
                     ui.label(format!(
-                        "{}: {} points", 
-                        self.plot_window.names[i],
+                        "{}: {} points",
+                        name,
                         self.plot_window.plot_data[i].values.len()
                     ));
                 }
@@ -336,8 +491,8 @@ impl SerialMonitorApp {
                 ui.separator();
 
                 let available_width = ui.available_width();  // 获取可用宽度
-                for i in 0..4 {
-                    Plot::new(self.plot_window.names[i])
+                for (i, name) in names.iter().enumerate() {
+                    Plot::new(name.clone())
                         .height(150.0)
                         .width(available_width)  // 设置宽度为可用宽度
                         .show_axes([true, true])
@@ -352,15 +507,39 @@ impl SerialMonitorApp {
     }
 
     fn reset_device(&mut self) {
-        if let Some(port) = &self.port {
-            if let Ok(mut port) = port.lock() {
-                // Reset sequence
-                let _ = port.clear(serialport::ClearBuffer::All);
-                let _ = port.write_data_terminal_ready(true);
-                thread::sleep(Duration::from_millis(100));
-                let _ = port.write_data_terminal_ready(false);
-                thread::sleep(Duration::from_millis(100));
+        if let Some(cmd_tx) = &self.cmd_tx {
+            let _ = cmd_tx.send(SerialPortCmd::ResetMcu);
+        }
+    }
+
+    // 切换日志开关：开启时按路径追加打开文件，关闭时释放
+    fn toggle_logging(&mut self) {
+        if self.is_logging {
+            match std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.log_path)
+            {
+                Ok(file) => self.log_file = Some(file),
+                Err(e) => {
+                    self.received_data.push_str(&format!("Log failed: {}\n", e));
+                    self.is_logging = false;
+                }
             }
+        } else {
+            self.log_file = None;
+        }
+    }
+
+    // 给每一帧打上 Unix 时间戳后追加写入日志文件
+    fn log_frame(&mut self, frame: &str) {
+        use std::io::Write;
+        if let Some(file) = &mut self.log_file {
+            let ts = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs_f64())
+                .unwrap_or(0.0);
+            let _ = writeln!(file, "{:.3},{}", ts, frame.trim_end());
         }
     }
 }
@@ -400,10 +579,10 @@ impl PlotData {
 struct PlotWindow {
     is_open: bool,
     format: String,
-    plot_data: [PlotData; 4],
-    names: [&'static str; 4],
+    plot_data: Vec<PlotData>,
     max_points: usize,
     is_paused: bool,  // 添加暂停状态
+    csv_path: String, // 导出 CSV 的文件路径
 }
 
 impl Default for PlotWindow {
@@ -411,14 +590,230 @@ impl Default for PlotWindow {
         Self {
             is_open: false,
             format: String::from("Pace: FL: %% FR: %% RL: %% RR: %%"),
-            plot_data: Default::default(),
-            names: ["FL", "FR", "RL", "RR"],
+            plot_data: Vec::new(),
             max_points: 1000,
             is_paused: false,  // 初始不暂停
+            csv_path: String::from("plot_export.csv"),
         }
     }
 }
 
+impl PlotWindow {
+    // format 中 %% 占位符的个数即通道数
+    fn channel_count(&self) -> usize {
+        self.format.matches("%%").count()
+    }
+
+    // 以 %% 切开 format 得到各占位符之间的字面分隔串（共 channel_count + 1 段）
+    fn separators(&self) -> Vec<String> {
+        self.format.split("%%").map(|s| s.to_string()).collect()
+    }
+
+    // 从每个占位符左侧的字面量里取末尾标签作为通道名，如 "Pace: FL: " -> "FL"
+    // 只保留字母数字，纯分隔符（如 CSV 的 ","）没有有效标签时退回 ch{i}
+    fn channel_names(&self) -> Vec<String> {
+        let separators = self.separators();
+        (0..self.channel_count())
+            .map(|i| {
+                separators[i]
+                    .split_whitespace()
+                    .last()
+                    .map(|t| t.chars().filter(|c| c.is_alphanumeric()).collect::<String>())
+                    .filter(|s| !s.is_empty())
+                    .unwrap_or_else(|| format!("ch{}", i))
+            })
+            .collect()
+    }
+
+    // 保证缓冲区数量与当前通道数一致（format 改动后调用）
+    fn ensure_channels(&mut self) {
+        let count = self.channel_count();
+        if self.plot_data.len() != count {
+            self.plot_data.resize_with(count, PlotData::default);
+        }
+    }
+
+    // 把各通道累积的 times/values 写成 CSV：时间列 + 每通道一列数值
+    fn export_csv(&self) -> std::io::Result<()> {
+        use std::io::Write;
+        let names = self.channel_names();
+        let mut file = std::fs::File::create(&self.csv_path)?;
+        writeln!(file, "time,{}", names.join(","))?;
+
+        // 各通道按采样序号对齐，时间列取第一个通道的时间戳
+        let rows = self.plot_data.iter().map(|d| d.values.len()).max().unwrap_or(0);
+        for i in 0..rows {
+            let time = self.plot_data.first().and_then(|d| d.times.get(i));
+            let mut line = match time {
+                Some(t) => t.to_string(),
+                None => String::new(),
+            };
+            for d in &self.plot_data {
+                line.push(',');
+                if let Some(v) = d.values.get(i) {
+                    line.push_str(&v.to_string());
+                }
+            }
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+// 按 format 推导出的字面分隔串解析一行，抽取每对分隔串之间的数值
+fn parse_by_format(line: &str, separators: &[String], count: usize) -> Option<Vec<f64>> {
+    let mut values = Vec::with_capacity(count);
+    let mut rest = line;
+    for i in 0..count {
+        let pre = &separators[i];
+        let post = &separators[i + 1];
+        // 定位并跳过左侧字面量
+        let start = rest.find(pre.as_str())? + pre.len();
+        rest = &rest[start..];
+        // 数值在右侧字面量之前；末尾占位符的右侧为空则取到空白或行尾
+        let end = if post.is_empty() {
+            rest.find(char::is_whitespace).unwrap_or(rest.len())
+        } else {
+            rest.find(post.as_str())?
+        };
+        let token = rest[..end].trim();
+        values.push(token.parse::<f64>().ok()?);
+        rest = &rest[end..];
+    }
+    Some(values)
+}
+
+// COBS 解帧器：从读线程累积字节，按 0x00 分隔符切出每一帧再做 COBS 解码
+#[derive(Default)]
+struct CobsDecoder {
+    buf: Vec<u8>,
+}
+
+impl CobsDecoder {
+    // 追加新读到的字节，返回本次凑齐的所有已解码帧
+    fn push(&mut self, bytes: &[u8]) -> Vec<Vec<u8>> {
+        self.buf.extend_from_slice(bytes);
+        let mut frames = Vec::new();
+        while let Some(pos) = self.buf.iter().position(|&b| b == 0) {
+            let frame: Vec<u8> = self.buf.drain(..pos).collect();
+            self.buf.remove(0); // 丢弃 0x00 分隔符
+            if frame.is_empty() {
+                continue;
+            }
+            if let Some(decoded) = cobs_decode(&frame) {
+                frames.push(decoded);
+            }
+        }
+        frames
+    }
+}
+
+// 单帧 COBS 解码：首字节是到下一个零的偏移，沿链前进把每个偏移位还原为 0x00，
+// 长度为 255 的分组不补零。返回 None 表示帧损坏。
+fn cobs_decode(frame: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(frame.len());
+    let mut i = 0;
+    while i < frame.len() {
+        let code = frame[i] as usize;
+        if code == 0 {
+            return None;
+        }
+        i += 1;
+        let block = code - 1;
+        if i + block > frame.len() {
+            return None;
+        }
+        out.extend_from_slice(&frame[i..i + block]);
+        i += block;
+        if code != 0xFF && i < frame.len() {
+            out.push(0);
+        }
+    }
+    Some(out)
+}
+
+// 用户可选的 postcard 消息类型，决定 COBS 帧如何反序列化与呈现
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum CobsMessageType {
+    #[default]
+    Telemetry,
+    ImuSample,
+}
+
+impl CobsMessageType {
+    const ALL: [CobsMessageType; 2] = [CobsMessageType::Telemetry, CobsMessageType::ImuSample];
+
+    fn label(&self) -> &'static str {
+        match self {
+            CobsMessageType::Telemetry => "Telemetry",
+            CobsMessageType::ImuSample => "ImuSample",
+        }
+    }
+
+    // 把一帧解码后的字节 postcard 反序列化成对应类型，格式化为可读一行
+    fn decode(&self, frame: &[u8]) -> String {
+        match self {
+            CobsMessageType::Telemetry => match postcard::from_bytes::<Telemetry>(frame) {
+                Ok(t) => format!(
+                    "Pace: FL: {} FR: {} RL: {} RR: {}",
+                    t.fl, t.fr, t.rl, t.rr
+                ),
+                Err(e) => format!("Decode error: {}", e),
+            },
+            CobsMessageType::ImuSample => match postcard::from_bytes::<ImuSample>(frame) {
+                Ok(s) => format!("IMU: ax: {} ay: {} az: {}", s.ax, s.ay, s.az),
+                Err(e) => format!("Decode error: {}", e),
+            },
+        }
+    }
+}
+
+// 文本模式下可选的字符编码，应对发送 GBK / Latin-1 等非 UTF-8 的设备
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+enum TextEncoding {
+    #[default]
+    Utf8,
+    Gbk,
+    Windows1252,
+}
+
+impl TextEncoding {
+    const ALL: [TextEncoding; 3] =
+        [TextEncoding::Utf8, TextEncoding::Gbk, TextEncoding::Windows1252];
+
+    fn label(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Gbk => "GBK",
+            TextEncoding::Windows1252 => "Windows-1252",
+        }
+    }
+
+    fn encoding(&self) -> &'static encoding_rs::Encoding {
+        match self {
+            TextEncoding::Utf8 => encoding_rs::UTF_8,
+            TextEncoding::Gbk => encoding_rs::GBK,
+            TextEncoding::Windows1252 => encoding_rs::WINDOWS_1252,
+        }
+    }
+}
+
+// postcard 反序列化目标，对应嵌入式固件常见的 serde 结构体
+#[derive(serde::Deserialize)]
+struct Telemetry {
+    fl: f32,
+    fr: f32,
+    rl: f32,
+    rr: f32,
+}
+
+#[derive(serde::Deserialize)]
+struct ImuSample {
+    ax: f32,
+    ay: f32,
+    az: f32,
+}
+
 fn is_root() -> bool {
     unsafe { libc::geteuid() == 0 }
 }
@@ -452,3 +847,68 @@ fn main() -> Result<(), eframe::Error> {
         Box::new(|_cc| Box::new(SerialMonitorApp::default())),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cobs_decodes_simple_frame() {
+        // [0x11, 0x22, 0x00, 0x33] 的 COBS 编码为 [0x03, 0x11, 0x22, 0x02, 0x33]
+        let frame = [0x03, 0x11, 0x22, 0x02, 0x33];
+        assert_eq!(cobs_decode(&frame), Some(vec![0x11, 0x22, 0x00, 0x33]));
+    }
+
+    #[test]
+    fn cobs_decodes_zero_bytes() {
+        // 两个 0x01 分组之间插入一个 0x00
+        assert_eq!(cobs_decode(&[0x01, 0x01]), Some(vec![0x00]));
+    }
+
+    #[test]
+    fn cobs_handles_255_boundary_without_inserting_zero() {
+        // 长度 255 的分组后紧跟另一分组时不补零
+        let mut frame = vec![0xFF];
+        frame.extend(1u8..=254);
+        frame.push(0x01);
+        assert_eq!(cobs_decode(&frame), Some((1u8..=254).collect::<Vec<u8>>()));
+    }
+
+    #[test]
+    fn cobs_rejects_malformed_frame() {
+        // 偏移超出帧长度视为损坏
+        assert_eq!(cobs_decode(&[0x05, 0x01]), None);
+    }
+
+    #[test]
+    fn parse_by_format_labeled() {
+        let separators: Vec<String> = "Pace: FL: %% FR: %% RL: %% RR: %%"
+            .split("%%")
+            .map(|s| s.to_string())
+            .collect();
+        let values = parse_by_format("Pace: FL: 1.0 FR: 2.0 RL: 3.0 RR: 4.0", &separators, 4);
+        assert_eq!(values, Some(vec![1.0, 2.0, 3.0, 4.0]));
+    }
+
+    #[test]
+    fn parse_by_format_csv() {
+        let separators: Vec<String> = "%%,%%,%%".split("%%").map(|s| s.to_string()).collect();
+        assert_eq!(parse_by_format("1,2,3", &separators, 3), Some(vec![1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn parse_by_format_trailing_placeholder() {
+        let separators: Vec<String> = "x=%%".split("%%").map(|s| s.to_string()).collect();
+        // 末尾占位符取到空白或行尾
+        assert_eq!(parse_by_format("x=42 done", &separators, 1), Some(vec![42.0]));
+    }
+
+    #[test]
+    fn parse_by_format_returns_none_on_mismatch() {
+        let separators: Vec<String> = "Pace: FL: %% FR: %% RL: %% RR: %%"
+            .split("%%")
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(parse_by_format("garbage", &separators, 4), None);
+    }
+}